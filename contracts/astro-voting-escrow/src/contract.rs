@@ -0,0 +1,621 @@
+use std::convert::TryInto;
+
+use cosmwasm_std::{
+    entry_point, to_binary, Addr, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Order,
+    Response, StdError, StdResult, Uint128, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use cw_storage_plus::{Bound, U64Key};
+
+use astroport::asset::addr_validate_to_lower;
+
+use crate::delegation::{
+    execute_delegate_voting_power, execute_undelegate, query_adjusted_balance,
+    query_delegated_voting_power,
+};
+use crate::error::ContractError;
+use crate::msg::{
+    AllLocksWithPowerResponse, ExecuteMsg, InstantiateMsg, LockWithPowerResponse, QueryMsg,
+};
+use crate::rewards::{execute_claim_rewards, receive_cw20};
+use crate::state::{
+    BoostCurvePoint, Config, Lock, Point, RewardsConfig, ANCHOR, BLACKLIST, BOOST_CURVE, CONFIG,
+    DELEGATIONS, GLOBAL_HISTORY, HISTORY, HISTORY_LEN, LAST_CLAIMED_PERIOD, LOCKED,
+    REWARDS_CONFIG, SLOPE_CHANGES,
+};
+use crate::utils::{
+    blacklist_check, calc_coefficient, calc_voting_power, effective_point,
+    fetch_last_global_checkpoint, get_period, time_limits_check, validate_boost_curve,
+    voting_power_at, xastro_token_check,
+};
+
+/// Seconds in a week, the minimum lock time and the checkpoint granularity
+pub const WEEK: u64 = 7 * 86400;
+
+/// Seconds in 2 years, the maximum lock time
+pub const MAX_LOCK_TIME: u64 = 2 * 365 * 86400;
+
+/// The maximum number of detailed checkpoints kept per address in [`HISTORY`] before the oldest
+/// one is evicted into [`ANCHOR`], bounding per-address storage and `fetch_last_checkpoint` scans
+pub const MAX_CHECKPOINT_HISTORY: u64 = 128;
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let config = Config {
+        owner: addr_validate_to_lower(deps.api, &msg.owner)?,
+        deposit_token_addr: addr_validate_to_lower(deps.api, &msg.deposit_token_addr)?,
+    };
+    CONFIG.save(deps.storage, &config)?;
+    BLACKLIST.save(deps.storage, &vec![])?;
+
+    let rewards_config = RewardsConfig {
+        reward_token_addr: addr_validate_to_lower(deps.api, &msg.reward_token_addr)?,
+        zero_power_policy: msg.zero_power_policy,
+        max_periods_per_claim: msg.max_periods_per_claim,
+    };
+    REWARDS_CONFIG.save(deps.storage, &rewards_config)?;
+
+    // Reproduces the historical hard-coded ramp: 1x at a 0-length lock, 2.5x at MAX_LOCK_TIME.
+    BOOST_CURVE.save(
+        deps.storage,
+        &vec![
+            BoostCurvePoint {
+                period_fraction: Decimal::zero(),
+                multiplier: Decimal::one(),
+            },
+            BoostCurvePoint {
+                period_fraction: Decimal::one(),
+                multiplier: Decimal::percent(250),
+            },
+        ],
+    )?;
+
+    Ok(Response::default())
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::CreateLock { amount, time } => {
+            execute_create_lock(deps, env, info, amount, time)
+        }
+        ExecuteMsg::ExtendLockTime { time } => execute_extend_lock_time(deps, env, info, time),
+        ExecuteMsg::Withdraw {} => execute_withdraw(deps, env, info),
+        ExecuteMsg::UpdateBlacklist {
+            append_addrs,
+            remove_addrs,
+        } => execute_update_blacklist(deps, info, append_addrs, remove_addrs),
+        ExecuteMsg::DelegateVotingPower {
+            receiver,
+            bps,
+            expire_period,
+        } => execute_delegate_voting_power(deps, env, info, receiver, bps, expire_period),
+        ExecuteMsg::Undelegate {} => execute_undelegate(deps, env, info),
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, info, msg),
+        ExecuteMsg::ClaimRewards {} => execute_claim_rewards(deps, env, info),
+        ExecuteMsg::UpdateBoostCurve { points } => execute_update_boost_curve(deps, info, points),
+    }
+}
+
+fn execute_create_lock(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+    time: u64,
+) -> Result<Response, ContractError> {
+    xastro_token_check(deps.as_ref(), info.sender.clone())?;
+    blacklist_check(deps.as_ref(), &info.sender)?;
+    time_limits_check(time)?;
+
+    if LOCKED.has(deps.storage, &info.sender) {
+        return Err(ContractError::LockAlreadyExists {});
+    }
+
+    let block_period = get_period(env.block.time.seconds());
+    let end = block_period + get_period(time);
+
+    let lock = Lock {
+        amount,
+        start: block_period,
+        end,
+    };
+    LOCKED.save(deps.storage, &info.sender, &lock)?;
+
+    checkpoint(deps, &info.sender, block_period, amount, end)?;
+
+    Ok(Response::new().add_attribute("action", "create_lock"))
+}
+
+fn execute_extend_lock_time(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    time: u64,
+) -> Result<Response, ContractError> {
+    blacklist_check(deps.as_ref(), &info.sender)?;
+    let mut lock = LOCKED
+        .load(deps.storage, &info.sender)
+        .map_err(|_| ContractError::LockDoesntExist {})?;
+
+    let block_period = get_period(env.block.time.seconds());
+    if lock.end <= block_period {
+        return Err(ContractError::LockExpired {});
+    }
+
+    // `checkpoint` always recomputes power/slope fresh from the full locked `amount`, which would
+    // blow away an active delegation's carve-out and double-count voting power between the
+    // delegator and the receiver. Require `Undelegate` first instead.
+    if let Some(delegation) = DELEGATIONS.may_load(deps.storage, &info.sender)? {
+        if delegation.expire_period > block_period {
+            return Err(ContractError::DelegationAlreadyActive(
+                info.sender.to_string(),
+            ));
+        }
+    }
+
+    let new_end = lock.end + get_period(time);
+    time_limits_check((new_end - block_period) * WEEK)?;
+
+    lock.end = new_end;
+    LOCKED.save(deps.storage, &info.sender, &lock)?;
+
+    checkpoint(deps, &info.sender, block_period, lock.amount, new_end)?;
+
+    Ok(Response::new().add_attribute("action", "extend_lock_time"))
+}
+
+fn execute_withdraw(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let lock = LOCKED
+        .load(deps.storage, &info.sender)
+        .map_err(|_| ContractError::LockDoesntExist {})?;
+
+    let block_period = get_period(env.block.time.seconds());
+    if lock.end > block_period {
+        return Err(ContractError::LockHasNotExpired {});
+    }
+
+    LOCKED.remove(deps.storage, &info.sender);
+
+    let config = CONFIG.load(deps.storage)?;
+    Ok(Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: config.deposit_token_addr.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: info.sender.to_string(),
+                amount: lock.amount,
+            })?,
+            funds: vec![],
+        })
+        .add_attribute("action", "withdraw"))
+}
+
+fn execute_update_blacklist(
+    deps: DepsMut,
+    info: MessageInfo,
+    append_addrs: Option<Vec<String>>,
+    remove_addrs: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut blacklist = BLACKLIST.load(deps.storage)?;
+    if let Some(addrs) = append_addrs {
+        for addr in crate::utils::validate_addresses(deps.as_ref(), &addrs)? {
+            if !blacklist.contains(&addr) {
+                blacklist.push(addr);
+            }
+        }
+    }
+    if let Some(addrs) = remove_addrs {
+        let to_remove = crate::utils::validate_addresses(deps.as_ref(), &addrs)?;
+        blacklist.retain(|addr| !to_remove.contains(addr));
+    }
+    BLACKLIST.save(deps.storage, &blacklist)?;
+
+    Ok(Response::new().add_attribute("action", "update_blacklist"))
+}
+
+/// Updates the lock-time boost curve `calc_coefficient` interpolates between. Only affects
+/// checkpoints written from this point on; existing [`HISTORY`] entries already hold their
+/// computed power and are never retroactively re-evaluated.
+fn execute_update_boost_curve(
+    deps: DepsMut,
+    info: MessageInfo,
+    points: Vec<BoostCurvePoint>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    validate_boost_curve(&points)?;
+    BOOST_CURVE.save(deps.storage, &points)?;
+
+    Ok(Response::new().add_attribute("action", "update_boost_curve"))
+}
+
+/// Writes a fresh checkpoint for `addr` reflecting a lock of `amount` starting at `block_period`
+/// and expiring at `end`, and registers the matching slope change at `end`.
+pub(crate) fn checkpoint(
+    mut deps: DepsMut,
+    addr: &cosmwasm_std::Addr,
+    block_period: u64,
+    amount: Uint128,
+    end: u64,
+) -> Result<(), ContractError> {
+    let interval = end - block_period;
+    let curve = BOOST_CURVE.load(deps.storage)?;
+    let coefficient = calc_coefficient(interval, &curve);
+    let power = amount * coefficient;
+    let slope = Decimal::from_ratio(amount, interval) * coefficient;
+
+    let new_point = Point {
+        power,
+        start: block_period,
+        end,
+        slope,
+    };
+    write_checkpoint(deps.branch(), addr, new_point)?;
+
+    SLOPE_CHANGES.update(
+        deps.storage,
+        U64Key::new(end),
+        |existing| -> StdResult<Decimal> { Ok(existing.unwrap_or_default() + slope) },
+    )?;
+
+    Ok(())
+}
+
+/// Writes `point` as `addr`'s checkpoint at `point.start` in [`HISTORY`], running it through
+/// eviction and folding the delta it introduces into [`GLOBAL_HISTORY`]. Shared by [`checkpoint`]
+/// (lock-amount-driven checkpoints) and `delegation`'s execute handlers, so every write to
+/// [`HISTORY`] -- not just lock ones -- stays subject to [`MAX_CHECKPOINT_HISTORY`] eviction and
+/// keeps the global aggregate in sync.
+pub(crate) fn write_checkpoint(
+    deps: DepsMut,
+    addr: &Addr,
+    point: Point,
+) -> Result<(), ContractError> {
+    let period_key = U64Key::new(point.start);
+
+    let previous = effective_point(deps.as_ref(), addr, point.start)?;
+    let previous_power = previous.as_ref().map(|p| p.power).unwrap_or_default();
+    let previous_slope = previous.as_ref().map(|p| p.slope).unwrap_or_default();
+
+    let is_new_checkpoint = !HISTORY.has(deps.storage, (addr.clone(), period_key.clone()));
+    HISTORY.save(deps.storage, (addr.clone(), period_key.clone()), &point)?;
+    if is_new_checkpoint {
+        evict_oldest_checkpoint_if_needed(deps.storage, addr)?;
+    }
+
+    // Roll the delta this checkpoint introduces into the global aggregate so total-power queries
+    // (rewards finalization, `AllLocksWithPower`) don't need to replay every address's history.
+    let global_previous =
+        fetch_last_global_checkpoint(deps.as_ref(), &period_key)?.map(|(_, p)| p);
+    let global_power = global_previous
+        .as_ref()
+        .map(|p| calc_voting_power(p, point.start))
+        .unwrap_or_default();
+    let global_slope = global_previous.as_ref().map(|p| p.slope).unwrap_or_default();
+
+    let new_global_point = Point {
+        power: (global_power + point.power).saturating_sub(previous_power),
+        start: point.start,
+        end: point.end,
+        slope: (global_slope + point.slope).saturating_sub(previous_slope),
+    };
+    GLOBAL_HISTORY.save(deps.storage, period_key, &new_global_point)?;
+
+    Ok(())
+}
+
+/// Evicts the oldest [`HISTORY`] checkpoint for `addr` into [`ANCHOR`] once the address has more
+/// than [`MAX_CHECKPOINT_HISTORY`] detailed checkpoints stored.
+fn evict_oldest_checkpoint_if_needed(
+    storage: &mut dyn cosmwasm_std::Storage,
+    addr: &Addr,
+) -> Result<(), ContractError> {
+    let len = HISTORY_LEN.may_load(storage, addr)?.unwrap_or_default() + 1;
+    if len <= MAX_CHECKPOINT_HISTORY {
+        HISTORY_LEN.save(storage, addr, &len)?;
+        return Ok(());
+    }
+
+    let oldest = HISTORY
+        .prefix(addr.clone())
+        .range(storage, None, None, Order::Ascending)
+        .next()
+        .transpose()?;
+
+    if let Some((period_bytes, oldest_point)) = oldest {
+        let period_bytes: [u8; 8] = period_bytes
+            .try_into()
+            .map_err(|_| ContractError::Std(StdError::generic_err("Deserialization error")))?;
+        let oldest_period = u64::from_be_bytes(period_bytes);
+
+        ANCHOR.save(storage, addr, &oldest_point)?;
+        HISTORY.remove(storage, (addr.clone(), U64Key::new(oldest_period)));
+        HISTORY_LEN.save(storage, addr, &MAX_CHECKPOINT_HISTORY)?;
+    }
+
+    Ok(())
+}
+
+#[entry_point]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::UserVotingPower { user } => {
+            to_binary(&query_user_voting_power(deps, env, user)?)
+        }
+        QueryMsg::LockInfo { user } => to_binary(&query_lock_info(deps, user)?),
+        QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::DelegatedVotingPower { account, period } => {
+            to_binary(&query_delegated_voting_power(deps, account, period)?)
+        }
+        QueryMsg::AdjustedBalance { account } => {
+            to_binary(&query_adjusted_balance(deps, env, account)?)
+        }
+        QueryMsg::RewardsConfig {} => to_binary(&REWARDS_CONFIG.load(deps.storage)?),
+        QueryMsg::LastClaimedPeriod { account } => {
+            let addr = addr_validate_to_lower(deps.api, &account)?;
+            to_binary(&LAST_CLAIMED_PERIOD.may_load(deps.storage, &addr)?.unwrap_or_default())
+        }
+        QueryMsg::AllLocksWithPower {
+            start_after,
+            limit,
+            at_period,
+        } => to_binary(&query_all_locks_with_power(
+            deps,
+            start_after,
+            limit,
+            at_period,
+        )?),
+        QueryMsg::BoostCurve {} => to_binary(&BOOST_CURVE.load(deps.storage)?),
+    }
+}
+
+fn query_user_voting_power(deps: Deps, env: Env, user: String) -> StdResult<Uint128> {
+    let addr = addr_validate_to_lower(deps.api, &user)?;
+    let block_period = get_period(env.block.time.seconds());
+    voting_power_at(deps, &addr, block_period)
+}
+
+fn query_lock_info(deps: Deps, user: String) -> StdResult<Lock> {
+    let addr = addr_validate_to_lower(deps.api, &user)?;
+    LOCKED.load(deps.storage, &addr)
+}
+
+/// The default and maximum page size for [`QueryMsg::AllLocksWithPower`]
+const DEFAULT_LIMIT: u32 = 30;
+const MAX_LIMIT: u32 = 100;
+
+/// The maximum number of raw [`LOCKED`] entries a single [`QueryMsg::AllLocksWithPower`] call will
+/// visit, regardless of how many of them turn out zero-power or blacklisted. Bounds the call's gas
+/// cost independently of `limit`, which only caps the number of *results* returned.
+const MAX_SCANNED_ENTRIES: usize = 1000;
+
+/// Returns every address's lock enriched with its live voting power at `at_period`, skipping
+/// addresses whose power has already decayed to zero. Blacklisted addresses count as zero power
+/// and are skipped the same way.
+/// `next_start_after` distinguishes a capped scan (pass it back as `start_after` to continue) from
+/// genuine exhaustion (`None`), since an empty page can otherwise mean either one.
+fn query_all_locks_with_power(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    at_period: u64,
+) -> StdResult<AllLocksWithPowerResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .map(|addr| addr_validate_to_lower(deps.api, &addr))
+        .transpose()?;
+    let start_bound = start.as_ref().map(|addr| Bound::exclusive(addr.as_bytes()));
+
+    let blacklist = BLACKLIST.load(deps.storage)?;
+
+    let mut locks = Vec::with_capacity(limit);
+    let mut scanned = 0usize;
+    let mut cursor = None;
+    let mut capped = false;
+
+    for item in LOCKED.range(deps.storage, start_bound, None, Order::Ascending) {
+        if locks.len() >= limit || scanned >= MAX_SCANNED_ENTRIES {
+            capped = true;
+            break;
+        }
+        scanned += 1;
+
+        let (addr_raw, lock) = item?;
+        let addr = Addr::unchecked(
+            String::from_utf8(addr_raw).map_err(|_| StdError::generic_err("Deserialization error"))?,
+        );
+        cursor = Some(addr.clone());
+
+        let power = if blacklist.contains(&addr) {
+            Uint128::zero()
+        } else {
+            voting_power_at(deps, &addr, at_period)?
+        };
+
+        if power.is_zero() {
+            continue;
+        }
+
+        locks.push(LockWithPowerResponse {
+            address: addr,
+            lock,
+            power,
+        });
+    }
+
+    Ok(AllLocksWithPowerResponse {
+        locks,
+        next_start_after: if capped { cursor } else { None },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    fn seed_lock(deps: DepsMut, addr: &Addr, lock: Lock) {
+        LOCKED.save(deps.storage, addr, &lock).unwrap();
+    }
+
+    /// A page capped by `limit` must still report a `next_start_after`, even though every entry
+    /// scanned had nonzero power -- an empty-looking cap isn't the only case that needs a cursor.
+    #[test]
+    fn limit_cap_reports_cursor_when_more_entries_remain() {
+        let mut deps = mock_dependencies(&[]);
+        BLACKLIST.save(deps.as_mut().storage, &vec![]).unwrap();
+
+        let addr_a = Addr::unchecked("addr_a");
+        let addr_b = Addr::unchecked("addr_b");
+        let point = Point {
+            power: Uint128::new(100),
+            start: 0,
+            end: 100,
+            slope: Decimal::zero(),
+        };
+        for addr in [&addr_a, &addr_b] {
+            seed_lock(
+                deps.as_mut(),
+                addr,
+                Lock {
+                    amount: Uint128::new(100),
+                    start: 0,
+                    end: 100,
+                },
+            );
+            HISTORY
+                .save(deps.as_mut().storage, (addr.clone(), U64Key::new(0)), &point)
+                .unwrap();
+        }
+
+        let response =
+            query_all_locks_with_power(deps.as_ref(), None, Some(1), 0).unwrap();
+
+        assert_eq!(response.locks.len(), 1);
+        assert_eq!(response.next_start_after, Some(addr_a));
+    }
+
+    /// Once every `LOCKED` entry has genuinely been visited, `next_start_after` must be `None` --
+    /// even if the final page itself is smaller than `limit`.
+    #[test]
+    fn exhausted_scan_reports_no_cursor() {
+        let mut deps = mock_dependencies(&[]);
+        BLACKLIST.save(deps.as_mut().storage, &vec![]).unwrap();
+
+        let addr = Addr::unchecked("addr_a");
+        seed_lock(
+            deps.as_mut(),
+            &addr,
+            Lock {
+                amount: Uint128::new(100),
+                start: 0,
+                end: 100,
+            },
+        );
+        HISTORY
+            .save(
+                deps.as_mut().storage,
+                (addr.clone(), U64Key::new(0)),
+                &Point {
+                    power: Uint128::new(100),
+                    start: 0,
+                    end: 100,
+                    slope: Decimal::zero(),
+                },
+            )
+            .unwrap();
+
+        let response =
+            query_all_locks_with_power(deps.as_ref(), None, Some(30), 0).unwrap();
+
+        assert_eq!(response.locks.len(), 1);
+        assert_eq!(response.next_start_after, None);
+    }
+
+    /// A scan can exhaust `MAX_SCANNED_ENTRIES` while every entry visited turns out zero-power,
+    /// yielding an empty page that must still carry a cursor -- otherwise it's indistinguishable
+    /// from genuine exhaustion and a caller would stop paginating too early.
+    #[test]
+    fn scan_cap_with_only_zero_power_entries_still_signals_more_remains() {
+        let mut deps = mock_dependencies(&[]);
+        BLACKLIST.save(deps.as_mut().storage, &vec![]).unwrap();
+
+        // `MAX_SCANNED_ENTRIES` zero-power locks (no HISTORY checkpoint at all), in lexical order.
+        for i in 0..MAX_SCANNED_ENTRIES {
+            let addr = Addr::unchecked(format!("addr_{:04}", i));
+            seed_lock(
+                deps.as_mut(),
+                &addr,
+                Lock {
+                    amount: Uint128::new(100),
+                    start: 0,
+                    end: 100,
+                },
+            );
+        }
+
+        // One more lock past the scan cap, with real power.
+        let last_addr = Addr::unchecked(format!("addr_{:04}", MAX_SCANNED_ENTRIES));
+        seed_lock(
+            deps.as_mut(),
+            &last_addr,
+            Lock {
+                amount: Uint128::new(100),
+                start: 0,
+                end: 100,
+            },
+        );
+        HISTORY
+            .save(
+                deps.as_mut().storage,
+                (last_addr.clone(), U64Key::new(0)),
+                &Point {
+                    power: Uint128::new(100),
+                    start: 0,
+                    end: 100,
+                    slope: Decimal::zero(),
+                },
+            )
+            .unwrap();
+
+        let first_page =
+            query_all_locks_with_power(deps.as_ref(), None, Some(DEFAULT_LIMIT), 0).unwrap();
+        assert!(first_page.locks.is_empty());
+        let cursor = first_page
+            .next_start_after
+            .expect("scan cap must still signal more entries remain");
+
+        let second_page = query_all_locks_with_power(
+            deps.as_ref(),
+            Some(cursor.to_string()),
+            Some(DEFAULT_LIMIT),
+            0,
+        )
+        .unwrap();
+        assert_eq!(second_page.locks.len(), 1);
+        assert_eq!(second_page.locks[0].address, last_addr);
+        assert_eq!(second_page.next_start_after, None);
+    }
+}
@@ -0,0 +1,401 @@
+use cosmwasm_std::{Decimal, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128};
+use cw_storage_plus::U64Key;
+
+use astroport::asset::addr_validate_to_lower;
+
+use crate::contract::write_checkpoint;
+use crate::error::ContractError;
+use crate::state::{Delegation, Point, SlopeAdjustment, ADDR_SLOPE_CHANGES, DELEGATIONS, LOCKED};
+use crate::utils::{blacklist_check, calc_voting_power, effective_point, get_period, voting_power_at};
+
+/// Delegates `bps` (in basis points) of the sender's current voting power to `receiver` until
+/// `expire_period`. Writes a subtracting checkpoint for the sender and an adding checkpoint for
+/// `receiver`, and schedules matching [`crate::state::ADDR_SLOPE_CHANGES`] entries at
+/// `expire_period` so the delegation reverts automatically once it expires.
+pub fn execute_delegate_voting_power(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    receiver: String,
+    bps: u16,
+    expire_period: u64,
+) -> Result<Response, ContractError> {
+    if bps == 0 || bps > 10000 {
+        return Err(ContractError::InvalidBPS {});
+    }
+
+    blacklist_check(deps.as_ref(), &info.sender)?;
+    let receiver = addr_validate_to_lower(deps.api, &receiver)?;
+    blacklist_check(deps.as_ref(), &receiver)?;
+
+    let block_period = get_period(env.block.time.seconds());
+
+    if let Some(existing) = DELEGATIONS.may_load(deps.storage, &info.sender)? {
+        if existing.expire_period > block_period {
+            return Err(ContractError::DelegationAlreadyActive(
+                info.sender.to_string(),
+            ));
+        }
+    }
+
+    let lock = LOCKED
+        .load(deps.storage, &info.sender)
+        .map_err(|_| ContractError::LockDoesntExist {})?;
+    if expire_period > lock.end {
+        return Err(ContractError::DelegationExceedsLockEnd {});
+    }
+
+    let share = Decimal::from_ratio(bps as u128, 10000_u128);
+
+    // Reads through `effective_point`, not a raw checkpoint, so a prior delegation that's already
+    // auto-reverted via `ADDR_SLOPE_CHANGES` (but hasn't had a fresh checkpoint written since) is
+    // accounted for instead of silently baking its stale, still-reduced power/slope into this one.
+    let sender_point = effective_point(deps.as_ref(), &info.sender, block_period)?
+        .ok_or(ContractError::LockDoesntExist {})?;
+    if sender_point.power.is_zero() {
+        return Err(ContractError::ZeroVotingPower {});
+    }
+
+    // This is also the delegator's undelegated remaining power at any future period: scaling the
+    // full point down by `share` leaves exactly the complementary (1 - share) fraction behind.
+    let delegated_power = sender_point.power * share;
+    let delegated_slope = sender_point.slope * share;
+
+    // Subtracting checkpoint for the delegator: decays at the reduced slope until the slope
+    // change registered below restores the original slope at `expire_period`.
+    let sender_new_point = Point {
+        power: sender_point.power.saturating_sub(delegated_power),
+        start: block_period,
+        end: sender_point.end,
+        slope: sender_point.slope.saturating_sub(delegated_slope),
+    };
+    write_checkpoint(deps.branch(), &info.sender, sender_new_point)?;
+
+    // Adding checkpoint for the receiver: the delegated power and slope are layered on top of
+    // whatever voting power the receiver already has of its own.
+    let receiver_point = effective_point(deps.as_ref(), &receiver, block_period)?.unwrap_or(Point {
+        power: Uint128::zero(),
+        start: block_period,
+        end: 0,
+        slope: Decimal::zero(),
+    });
+    let receiver_new_point = Point {
+        power: receiver_point.power + delegated_power,
+        start: block_period,
+        end: receiver_point.end.max(expire_period),
+        slope: receiver_point.slope + delegated_slope,
+    };
+    write_checkpoint(deps.branch(), &receiver, receiver_new_point)?;
+
+    // A delegated slice's power/slope ratio is inherited from the sender's point at grant time, so
+    // on its own it would only reach zero at the sender's original lock end, not at
+    // `expire_period` (which is allowed to be earlier). Whatever power is still outstanding at
+    // `expire_period` has to be moved back explicitly alongside the slope flip, or it's stranded
+    // permanently on the receiver's side.
+    let residual_at_expiry = calc_voting_power(
+        &Point {
+            power: delegated_power,
+            start: block_period,
+            end: expire_period,
+            slope: delegated_slope,
+        },
+        expire_period,
+    );
+
+    // At `expire_period` the delegator's slope steepens back to its original value and the
+    // receiver's slope flattens back out, with the still-outstanding power moved back alongside
+    // it, so `voting_power_at` lands both powers where they'd be without the delegation ever
+    // having happened -- no `Undelegate` call required.
+    ADDR_SLOPE_CHANGES.update(
+        deps.storage,
+        (info.sender.clone(), U64Key::new(expire_period)),
+        |existing| -> StdResult<SlopeAdjustment> {
+            let mut adjustment = existing.unwrap_or_default();
+            adjustment.slope_increase += delegated_slope;
+            adjustment.power_increase += residual_at_expiry;
+            Ok(adjustment)
+        },
+    )?;
+    ADDR_SLOPE_CHANGES.update(
+        deps.storage,
+        (receiver.clone(), U64Key::new(expire_period)),
+        |existing| -> StdResult<SlopeAdjustment> {
+            let mut adjustment = existing.unwrap_or_default();
+            adjustment.slope_decrease += delegated_slope;
+            adjustment.power_decrease += residual_at_expiry;
+            Ok(adjustment)
+        },
+    )?;
+
+    DELEGATIONS.save(
+        deps.storage,
+        &info.sender,
+        &Delegation {
+            receiver: receiver.clone(),
+            bps,
+            start: block_period,
+            expire_period,
+            power: delegated_power,
+            slope: delegated_slope,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "delegate_voting_power")
+        .add_attribute("delegator", info.sender)
+        .add_attribute("receiver", receiver)
+        .add_attribute("delegated_power", delegated_power))
+}
+
+/// Cancels the sender's active delegation ahead of its expiry, immediately restoring its own
+/// voting power and removing the power it lent out from the receiver.
+pub fn execute_undelegate(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let delegation = DELEGATIONS
+        .may_load(deps.storage, &info.sender)?
+        .ok_or_else(|| ContractError::NoActiveDelegation(info.sender.to_string()))?;
+
+    let block_period = get_period(env.block.time.seconds());
+
+    if block_period >= delegation.expire_period {
+        // Already auto-reverted via the `ADDR_SLOPE_CHANGES` entry scheduled at grant time; the
+        // `DELEGATIONS` entry is just stale bookkeeping at this point. Re-applying an adjustment
+        // here would double-revert it.
+        DELEGATIONS.remove(deps.storage, &info.sender);
+        return Ok(Response::new()
+            .add_attribute("action", "undelegate")
+            .add_attribute("delegator", info.sender));
+    }
+
+    // The delegated slice, decayed from its own grant-time point -- not the flat `delegation.power`
+    // captured at `start`, which overstates what's still in flight once time has passed.
+    let delegated_point = Point {
+        power: delegation.power,
+        start: delegation.start,
+        end: delegation.expire_period,
+        slope: delegation.slope,
+    };
+    let residual_now = calc_voting_power(&delegated_point, block_period);
+    // What the expiry adjustment scheduled at grant time would have moved, so it can be cancelled
+    // out exactly below.
+    let residual_at_expiry = calc_voting_power(&delegated_point, delegation.expire_period);
+
+    // Reads through `effective_point`, not a raw checkpoint, for the same reason as in
+    // `execute_delegate_voting_power`: a stale checkpoint can silently disagree with what
+    // `ADDR_SLOPE_CHANGES` has already auto-reverted since.
+    if let Some(sender_point) = effective_point(deps.as_ref(), &info.sender, block_period)? {
+        let restored = Point {
+            power: sender_point.power + residual_now,
+            start: block_period,
+            end: sender_point.end,
+            slope: sender_point.slope + delegation.slope,
+        };
+        write_checkpoint(deps.branch(), &info.sender, restored)?;
+    }
+
+    if let Some(receiver_point) =
+        effective_point(deps.as_ref(), &delegation.receiver, block_period)?
+    {
+        let reduced = Point {
+            power: receiver_point.power.saturating_sub(residual_now),
+            start: block_period,
+            end: receiver_point.end,
+            slope: receiver_point.slope.saturating_sub(delegation.slope),
+        };
+        write_checkpoint(deps.branch(), &delegation.receiver, reduced)?;
+    }
+
+    // The scheduled expiry adjustments haven't fired yet (`block_period < delegation.expire_period`
+    // was just checked above), so cancel exactly what was scheduled for them at grant time.
+    ADDR_SLOPE_CHANGES.update(
+        deps.storage,
+        (info.sender.clone(), U64Key::new(delegation.expire_period)),
+        |existing| -> StdResult<SlopeAdjustment> {
+            let mut adjustment = existing.unwrap_or_default();
+            adjustment.slope_increase = adjustment.slope_increase.saturating_sub(delegation.slope);
+            adjustment.power_increase =
+                adjustment.power_increase.saturating_sub(residual_at_expiry);
+            Ok(adjustment)
+        },
+    )?;
+    ADDR_SLOPE_CHANGES.update(
+        deps.storage,
+        (
+            delegation.receiver.clone(),
+            U64Key::new(delegation.expire_period),
+        ),
+        |existing| -> StdResult<SlopeAdjustment> {
+            let mut adjustment = existing.unwrap_or_default();
+            adjustment.slope_decrease = adjustment.slope_decrease.saturating_sub(delegation.slope);
+            adjustment.power_decrease =
+                adjustment.power_decrease.saturating_sub(residual_at_expiry);
+            Ok(adjustment)
+        },
+    )?;
+
+    DELEGATIONS.remove(deps.storage, &info.sender);
+
+    Ok(Response::new()
+        .add_attribute("action", "undelegate")
+        .add_attribute("delegator", info.sender))
+}
+
+/// Returns the voting power `account` has delegated out that's still in effect at `period`, or
+/// zero if it has no delegation or the delegation will already have expired by then.
+pub fn query_delegated_voting_power(deps: Deps, account: String, period: u64) -> StdResult<Uint128> {
+    let addr = addr_validate_to_lower(deps.api, &account)?;
+    match DELEGATIONS.may_load(deps.storage, &addr)? {
+        // Decayed from the grant-time slice, like `execute_undelegate`'s residual.
+        Some(d) if d.start <= period && d.expire_period > period => Ok(calc_voting_power(
+            &Point {
+                power: d.power,
+                start: d.start,
+                end: d.expire_period,
+                slope: d.slope,
+            },
+            period,
+        )),
+        _ => Ok(Uint128::zero()),
+    }
+}
+
+/// Returns `account`'s voting power at the current period adjusted for delegations: its own
+/// undelegated power (already net of anything it delegated out) plus whatever power other
+/// addresses have delegated to it. Since delegation writes a combined checkpoint, this is simply
+/// the account's current voting power.
+pub fn query_adjusted_balance(deps: Deps, env: Env, account: String) -> StdResult<Uint128> {
+    let addr = addr_validate_to_lower(deps.api, &account)?;
+    let block_period = get_period(env.block.time.seconds());
+    voting_power_at(deps, &addr, block_period)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{Addr, Timestamp};
+
+    use crate::state::{Lock, BLACKLIST, HISTORY};
+
+    /// Sets up a lock for `addr` with an initial `HISTORY` checkpoint matching `point`, and an
+    /// empty blacklist -- the minimum state `execute_delegate_voting_power` needs.
+    fn seed_lock(deps: cosmwasm_std::DepsMut, addr: &Addr, point: &Point) {
+        BLACKLIST.save(deps.storage, &vec![]).unwrap();
+        LOCKED
+            .save(
+                deps.storage,
+                addr,
+                &Lock {
+                    amount: point.power,
+                    start: point.start,
+                    end: point.end,
+                },
+            )
+            .unwrap();
+        HISTORY
+            .save(deps.storage, (addr.clone(), U64Key::new(point.start)), point)
+            .unwrap();
+    }
+
+    fn env_at_period(period: u64) -> Env {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(period * 7 * 86400);
+        env
+    }
+
+    /// A 50/50 delegation that's never explicitly undelegated must still conserve total power
+    /// across the delegator and receiver at every period, both before and after `expire_period`,
+    /// since the outstanding residual is transferred back automatically, not just the slope.
+    #[test]
+    fn delegation_conserves_total_power_through_auto_expiry() {
+        let mut deps = mock_dependencies(&[]);
+        let delegator = Addr::unchecked("delegator");
+        let receiver = Addr::unchecked("receiver");
+
+        let original = Point {
+            power: Uint128::new(1000),
+            start: 0,
+            end: 100,
+            slope: Decimal::from_ratio(10_u128, 1_u128),
+        };
+        seed_lock(deps.as_mut(), &delegator, &original);
+
+        execute_delegate_voting_power(
+            deps.as_mut(),
+            env_at_period(0),
+            mock_info(delegator.as_str(), &[]),
+            receiver.to_string(),
+            5000,
+            10,
+        )
+        .unwrap();
+
+        // Before expiry: the split is symmetric, so each side holds exactly half of whatever the
+        // original point would have decayed to.
+        let delegator_power = voting_power_at(deps.as_ref(), &delegator, 5).unwrap();
+        let receiver_power = voting_power_at(deps.as_ref(), &receiver, 5).unwrap();
+        assert_eq!(
+            delegator_power + receiver_power,
+            calc_voting_power(&original, 5)
+        );
+
+        // After expiry: the delegation has auto-reverted, with no fresh checkpoint written by
+        // either side since. The receiver must be back to zero and the delegator must be back to
+        // exactly what the original point would have decayed to on its own -- not a value
+        // permanently frozen at its `expire_period` residual.
+        let delegator_power = voting_power_at(deps.as_ref(), &delegator, 20).unwrap();
+        let receiver_power = voting_power_at(deps.as_ref(), &receiver, 20).unwrap();
+        assert_eq!(receiver_power, Uint128::zero());
+        assert_eq!(delegator_power, calc_voting_power(&original, 20));
+    }
+
+    /// Cancelling a delegation ahead of its expiry must credit/debit the residual decayed to the
+    /// current period, not the flat amount captured at grant time, and must do so immediately
+    /// (without waiting for a future checkpoint).
+    #[test]
+    fn undelegate_before_expiry_returns_decayed_residual_and_conserves_power() {
+        let mut deps = mock_dependencies(&[]);
+        let delegator = Addr::unchecked("delegator");
+        let receiver = Addr::unchecked("receiver");
+
+        let original = Point {
+            power: Uint128::new(1000),
+            start: 0,
+            end: 100,
+            slope: Decimal::from_ratio(10_u128, 1_u128),
+        };
+        seed_lock(deps.as_mut(), &delegator, &original);
+
+        execute_delegate_voting_power(
+            deps.as_mut(),
+            env_at_period(0),
+            mock_info(delegator.as_str(), &[]),
+            receiver.to_string(),
+            5000,
+            20,
+        )
+        .unwrap();
+
+        execute_undelegate(
+            deps.as_mut(),
+            env_at_period(5),
+            mock_info(delegator.as_str(), &[]),
+        )
+        .unwrap();
+
+        let delegator_power = voting_power_at(deps.as_ref(), &delegator, 5).unwrap();
+        let receiver_power = voting_power_at(deps.as_ref(), &receiver, 5).unwrap();
+        assert_eq!(receiver_power, Uint128::zero());
+        assert_eq!(delegator_power, calc_voting_power(&original, 5));
+
+        // The scheduled expiry adjustment must have been cancelled along with the early
+        // undelegate, so nothing extra happens to either side once `expire_period` (20) passes.
+        let delegator_power = voting_power_at(deps.as_ref(), &delegator, 30).unwrap();
+        let receiver_power = voting_power_at(deps.as_ref(), &receiver, 30).unwrap();
+        assert_eq!(receiver_power, Uint128::zero());
+        assert_eq!(delegator_power, calc_voting_power(&original, 30));
+    }
+}
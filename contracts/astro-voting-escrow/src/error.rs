@@ -0,0 +1,57 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+/// This enum describes contract errors
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Contract can't be migrated!")]
+    MigrationError {},
+
+    #[error("The lock time must be within limits (week <= lock time <= 2 years)")]
+    LockTimeLimitsError {},
+
+    #[error("The lock doesn't exist")]
+    LockDoesntExist {},
+
+    #[error("The lock already exists")]
+    LockAlreadyExists {},
+
+    #[error("The lock expired")]
+    LockExpired {},
+
+    #[error("The lock has not expired yet")]
+    LockHasNotExpired {},
+
+    #[error("Address {0} is blacklisted")]
+    AddressBlacklisted(String),
+
+    #[error("Append time must be within limits")]
+    AppendTimeLimitsError {},
+
+    #[error("Basic points must be within (0, 10000]")]
+    InvalidBPS {},
+
+    #[error("Delegation expire period can't be later than the delegator's lock end")]
+    DelegationExceedsLockEnd {},
+
+    #[error("{0} already has an active delegation")]
+    DelegationAlreadyActive(String),
+
+    #[error("{0} has no active delegation")]
+    NoActiveDelegation(String),
+
+    #[error("You can't delegate zero voting power")]
+    ZeroVotingPower {},
+
+    #[error("start_period must not be later than end_period")]
+    InvalidPeriodRange {},
+
+    #[error("Boost curve must start at (0, 1) and have non-decreasing period fractions and multipliers")]
+    InvalidBoostCurve {},
+}
@@ -0,0 +1,7 @@
+pub mod contract;
+pub mod delegation;
+pub mod error;
+pub mod msg;
+pub mod rewards;
+pub mod state;
+pub mod utils;
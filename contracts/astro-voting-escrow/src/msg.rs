@@ -0,0 +1,118 @@
+use cosmwasm_std::{Addr, Uint128};
+use cw20::Cw20ReceiveMsg;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::state::{BoostCurvePoint, Lock, ZeroPowerPolicy};
+
+/// This structure describes the parameters used for creating a contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// The vxASTRO contract owner
+    pub owner: String,
+    /// The xASTRO token contract address
+    pub deposit_token_addr: String,
+    /// The CW20 token rewards are paid out in
+    pub reward_token_addr: String,
+    /// What to do with a period's reward pool when nobody had voting power during it
+    pub zero_power_policy: ZeroPowerPolicy,
+    /// The maximum number of periods processed in a single [`ExecuteMsg::ClaimRewards`] call
+    pub max_periods_per_claim: u64,
+}
+
+/// This structure describes the execute messages available in the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Creates a new lock for an xASTRO deposit
+    CreateLock { amount: Uint128, time: u64 },
+    /// Extends the duration of an existing lock
+    ExtendLockTime { time: u64 },
+    /// Withdraws xASTRO from an expired lock
+    Withdraw {},
+    /// Updates the blacklist of addresses
+    UpdateBlacklist {
+        append_addrs: Option<Vec<String>>,
+        remove_addrs: Option<Vec<String>>,
+    },
+    /// Delegates a fraction of the sender's voting power to `receiver` until `expire_period`
+    DelegateVotingPower {
+        receiver: String,
+        bps: u16,
+        expire_period: u64,
+    },
+    /// Cancels the sender's active delegation ahead of its expiry
+    Undelegate {},
+    /// Receives a CW20 `Send` from the reward token contract, with `msg` decoding to a
+    /// [`Cw20HookMsg`]
+    Receive(Cw20ReceiveMsg),
+    /// Claims the sender's share of rewards for every unclaimed period up to the current one,
+    /// bounded by the config's `max_periods_per_claim`
+    ClaimRewards {},
+    /// Updates the piecewise-linear lock-time boost curve. Only affects checkpoints written
+    /// after this call; past voting-power history stays immutable.
+    UpdateBoostCurve { points: Vec<BoostCurvePoint> },
+}
+
+/// Hook messages embedded in an [`ExecuteMsg::Receive`]'s `msg` field
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    /// Funds the reward pool for every period in `[start_period, end_period]` with the sent
+    /// amount, split evenly across the range
+    FundRewards { start_period: u64, end_period: u64 },
+}
+
+/// This structure describes the query messages available in the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Return the vxASTRO voting power for `user` at the current period
+    UserVotingPower { user: String },
+    /// Return the raw lock information for `user`
+    LockInfo { user: String },
+    /// Return the contract configuration
+    Config {},
+    /// Return the voting power `account` has delegated out, still in effect at `period`
+    DelegatedVotingPower { account: String, period: u64 },
+    /// Return `account`'s voting power at the current period adjusted for delegations,
+    /// i.e. its own undelegated power plus any power delegated to it by others
+    AdjustedBalance { account: String },
+    /// Return the rewards module configuration
+    RewardsConfig {},
+    /// Return `account`'s claim cursor: the last period it has already been paid out for
+    LastClaimedPeriod { account: String },
+    /// Return every address's lock enriched with its live voting power at `at_period`, skipping
+    /// addresses whose power has already decayed to zero. Paginated by address; check the
+    /// response's `next_start_after` rather than an empty page to tell whether iteration is done.
+    AllLocksWithPower {
+        start_after: Option<String>,
+        limit: Option<u32>,
+        at_period: u64,
+    },
+    /// Return the current lock-time boost curve
+    BoostCurve {},
+}
+
+/// A single entry in the [`QueryMsg::AllLocksWithPower`] response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LockWithPowerResponse {
+    /// The lock owner
+    pub address: Addr,
+    /// The owner's raw lock
+    pub lock: Lock,
+    /// The owner's voting power at the requested period, always nonzero: zero-power and
+    /// blacklisted addresses are left out of the response
+    pub power: Uint128,
+}
+
+/// The response to [`QueryMsg::AllLocksWithPower`]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AllLocksWithPowerResponse {
+    /// The page of results
+    pub locks: Vec<LockWithPowerResponse>,
+    /// Pass as `start_after` on the next call to continue iterating. `None` means every `LOCKED`
+    /// entry has genuinely been visited; `Some` can still mean the page is empty, e.g. if every
+    /// entry scanned this call turned out zero-power or blacklisted.
+    pub next_start_after: Option<Addr>,
+}
@@ -0,0 +1,274 @@
+use cosmwasm_std::{
+    from_binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Storage, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_storage_plus::U64Key;
+
+use crate::error::ContractError;
+use crate::msg::Cw20HookMsg;
+use crate::state::{
+    PeriodReward, ZeroPowerPolicy, LAST_CLAIMED_PERIOD, PERIOD_REWARDS, REWARDS_CONFIG,
+};
+use crate::utils::{
+    fetch_last_global_checkpoint, fetch_slope_changes, get_period, voting_power_at,
+    DecimalRoundedCheckedMul,
+};
+
+/// Handles a CW20 `Send` from the reward token contract, dispatching on the embedded
+/// [`Cw20HookMsg`]. Only the configured reward token may invoke this -- `cw20_msg.sender` is the
+/// user who initiated the `Send` and isn't itself checked, since funding isn't credited to any
+/// particular account.
+pub fn receive_cw20(
+    deps: DepsMut,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config = REWARDS_CONFIG.load(deps.storage)?;
+    if info.sender != config.reward_token_addr {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match from_binary(&cw20_msg.msg)? {
+        Cw20HookMsg::FundRewards {
+            start_period,
+            end_period,
+        } => execute_fund_rewards(deps, start_period, end_period, cw20_msg.amount),
+    }
+}
+
+/// Funds the reward pool for every period in `[start_period, end_period]` with `amount`, split
+/// evenly across the range. Reward pool accounting is additive, so this can be called more than
+/// once for overlapping ranges.
+fn execute_fund_rewards(
+    deps: DepsMut,
+    start_period: u64,
+    end_period: u64,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if start_period > end_period {
+        return Err(ContractError::InvalidPeriodRange {});
+    }
+
+    let num_periods = end_period - start_period + 1;
+    let per_period = Uint128::from(amount.u128() / num_periods as u128);
+
+    for period in start_period..=end_period {
+        PERIOD_REWARDS.update(
+            deps.storage,
+            U64Key::new(period),
+            |existing| -> StdResult<PeriodReward> {
+                let mut reward = existing.unwrap_or_default();
+                reward.total_reward += per_period;
+                Ok(reward)
+            },
+        )?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "fund_rewards")
+        .add_attribute("start_period", start_period.to_string())
+        .add_attribute("end_period", end_period.to_string())
+        .add_attribute("amount", amount))
+}
+
+/// Claims the sender's share of rewards for every unclaimed period up to the current one,
+/// advancing its [`LAST_CLAIMED_PERIOD`] cursor and bounded by `max_periods_per_claim` so a
+/// long-dormant claimant can't blow the gas limit in a single call.
+pub fn execute_claim_rewards(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = REWARDS_CONFIG.load(deps.storage)?;
+    let current_period = get_period(env.block.time.seconds());
+    let mut cursor = LAST_CLAIMED_PERIOD
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+
+    let mut total_claim = Uint128::zero();
+    let mut periods_processed = 0u64;
+
+    while cursor < current_period && periods_processed < config.max_periods_per_claim {
+        cursor += 1;
+        periods_processed += 1;
+
+        let mut period_reward = match PERIOD_REWARDS.may_load(deps.storage, U64Key::new(cursor))? {
+            Some(reward) => reward,
+            None => continue,
+        };
+
+        let total_power = match period_reward.total_power {
+            Some(power) => power,
+            None => {
+                let power = total_voting_power_at(deps.as_ref(), cursor)?;
+                period_reward.total_power = Some(power);
+                if power.is_zero() {
+                    if let ZeroPowerPolicy::RollForward = config.zero_power_policy {
+                        roll_forward(deps.storage, cursor + 1, period_reward.total_reward)?;
+                    }
+                    period_reward.total_reward = Uint128::zero();
+                }
+                PERIOD_REWARDS.save(deps.storage, U64Key::new(cursor), &period_reward)?;
+                power
+            }
+        };
+
+        if total_power.is_zero() {
+            continue;
+        }
+
+        let user_power = voting_power_at(deps.as_ref(), &info.sender, cursor)?;
+        if user_power.is_zero() {
+            continue;
+        }
+
+        total_claim += period_reward.total_reward.multiply_ratio(user_power, total_power);
+    }
+
+    LAST_CLAIMED_PERIOD.save(deps.storage, &info.sender, &cursor)?;
+
+    let mut response = Response::new()
+        .add_attribute("action", "claim_rewards")
+        .add_attribute("amount", total_claim);
+
+    if !total_claim.is_zero() {
+        response = response.add_message(WasmMsg::Execute {
+            contract_addr: config.reward_token_addr.to_string(),
+            msg: cosmwasm_std::to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: info.sender.to_string(),
+                amount: total_claim,
+            })?,
+            funds: vec![],
+        });
+    }
+
+    Ok(response)
+}
+
+/// Rolls a forfeited period's reward pool into the next period's pool.
+fn roll_forward(storage: &mut dyn Storage, target_period: u64, amount: Uint128) -> StdResult<()> {
+    PERIOD_REWARDS.update(
+        storage,
+        U64Key::new(target_period),
+        |existing| -> StdResult<PeriodReward> {
+            let mut reward = existing.unwrap_or_default();
+            reward.total_reward += amount;
+            Ok(reward)
+        },
+    )?;
+    Ok(())
+}
+
+/// Computes the total voting power across every address at `period`, starting from the last
+/// [`crate::state::GLOBAL_HISTORY`] checkpoint and replaying intervening slope changes so the
+/// aggregate decay matches the sum of every individual `calc_voting_power`.
+pub(crate) fn total_voting_power_at(deps: Deps, period: u64) -> StdResult<Uint128> {
+    let period_key = U64Key::new(period);
+    let checkpoint = match fetch_last_global_checkpoint(deps, &period_key)? {
+        Some((_, point)) => point,
+        None => return Ok(Uint128::zero()),
+    };
+
+    let mut power = checkpoint.power;
+    let mut slope = checkpoint.slope;
+    let mut cursor = checkpoint.start;
+
+    for (change_period, slope_change) in fetch_slope_changes(deps, checkpoint.start, period)? {
+        let shift = slope
+            .checked_mul(Uint128::from(change_period - cursor))
+            .unwrap_or_else(|_| Uint128::zero());
+        power = power.checked_sub(shift).unwrap_or_else(|_| Uint128::zero());
+        slope = if slope > slope_change {
+            slope - slope_change
+        } else {
+            cosmwasm_std::Decimal::zero()
+        };
+        cursor = change_period;
+    }
+
+    let shift = slope
+        .checked_mul(Uint128::from(period - cursor))
+        .unwrap_or_else(|_| Uint128::zero());
+    Ok(power.checked_sub(shift).unwrap_or_else(|_| Uint128::zero()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_info};
+    use cosmwasm_std::{to_binary, Addr};
+    use crate::state::RewardsConfig;
+
+    fn seed_config(deps: DepsMut) {
+        REWARDS_CONFIG
+            .save(
+                deps.storage,
+                &RewardsConfig {
+                    reward_token_addr: Addr::unchecked("reward_token"),
+                    zero_power_policy: ZeroPowerPolicy::Burn,
+                    max_periods_per_claim: 10,
+                },
+            )
+            .unwrap();
+    }
+
+    /// A `Receive` from the configured reward token, wrapping a `FundRewards` hook message, must
+    /// fund every period in the requested range by an even split of the sent amount.
+    #[test]
+    fn receive_cw20_funds_rewards_for_every_period_in_range() {
+        let mut deps = mock_dependencies(&[]);
+        seed_config(deps.as_mut());
+
+        let cw20_msg = Cw20ReceiveMsg {
+            sender: "depositor".to_string(),
+            amount: Uint128::new(300),
+            msg: to_binary(&Cw20HookMsg::FundRewards {
+                start_period: 1,
+                end_period: 3,
+            })
+            .unwrap(),
+        };
+
+        receive_cw20(
+            deps.as_mut(),
+            mock_info("reward_token", &[]),
+            cw20_msg,
+        )
+        .unwrap();
+
+        for period in 1..=3 {
+            let reward = PERIOD_REWARDS
+                .load(deps.as_ref().storage, U64Key::new(period))
+                .unwrap();
+            assert_eq!(reward.total_reward, Uint128::new(100));
+        }
+    }
+
+    /// Tokens arriving from anything other than the configured reward token contract must be
+    /// rejected -- this is the only gate standing between the reward pool and an arbitrary CW20
+    /// crediting itself as funding.
+    #[test]
+    fn receive_cw20_rejects_unexpected_sender() {
+        let mut deps = mock_dependencies(&[]);
+        seed_config(deps.as_mut());
+
+        let cw20_msg = Cw20ReceiveMsg {
+            sender: "depositor".to_string(),
+            amount: Uint128::new(300),
+            msg: to_binary(&Cw20HookMsg::FundRewards {
+                start_period: 1,
+                end_period: 3,
+            })
+            .unwrap(),
+        };
+
+        let err = receive_cw20(
+            deps.as_mut(),
+            mock_info("not_the_reward_token", &[]),
+            cw20_msg,
+        )
+        .unwrap_err();
+
+        assert!(matches!(err, ContractError::Unauthorized {}));
+    }
+}
@@ -0,0 +1,168 @@
+use cosmwasm_std::{Addr, Decimal, Uint128};
+use cw_storage_plus::{Item, Map, U64Key};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// This structure stores the parameters of the vxASTRO voting power at a given point in time
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct Point {
+    /// The voting power at `start`
+    pub power: Uint128,
+    /// The period the checkpoint was taken at
+    pub start: u64,
+    /// The period the underlying lock expires at
+    pub end: u64,
+    /// The slope at which the voting power decays, in voting power units per period
+    pub slope: Decimal,
+}
+
+/// This structure stores the main parameters for the voting escrow contract
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    /// The address that's allowed to change contract parameters
+    pub owner: Addr,
+    /// The xASTRO token contract address
+    pub deposit_token_addr: Addr,
+}
+
+/// This structure stores the lock information for a given address
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct Lock {
+    /// The amount of xASTRO locked
+    pub amount: Uint128,
+    /// The period the lock was created at
+    pub start: u64,
+    /// The period the lock expires at
+    pub end: u64,
+}
+
+/// Stores the contract config
+pub const CONFIG: Item<Config> = Item::new("config");
+
+/// Stores each address's raw xASTRO lock
+pub const LOCKED: Map<&Addr, Lock> = Map::new("locked");
+
+/// HISTORY stores checkpoints of every address's voting power, keyed by period. Bounded to the
+/// newest [`crate::contract::MAX_CHECKPOINT_HISTORY`] entries per address; older checkpoints are
+/// evicted into [`ANCHOR`].
+pub const HISTORY: Map<(Addr, U64Key), Point> = Map::new("history");
+
+/// Tracks how many detailed checkpoints are currently stored in [`HISTORY`] for each address, so
+/// eviction can tell when the cap has been exceeded without a range scan.
+pub const HISTORY_LEN: Map<&Addr, u64> = Map::new("history_len");
+
+/// Stores, per address, the most recently evicted checkpoint from [`HISTORY`]. Since each
+/// checkpoint already encodes the address's total power/slope as of that period (not a delta),
+/// the latest eviction alone is enough to compute power at any period from its own `start` on.
+/// Periods before the anchor's `start` can no longer be reconstructed.
+pub const ANCHOR: Map<&Addr, Point> = Map::new("anchor");
+
+/// SLOPE_CHANGES stores the total slope change expected at a given period
+pub const SLOPE_CHANGES: Map<U64Key, Decimal> = Map::new("slope_changes");
+
+/// Stores blacklisted addresses
+pub const BLACKLIST: Item<Vec<Addr>> = Item::new("blacklist");
+
+/// This structure describes a voting power delegation from one address to another
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Delegation {
+    /// The address receiving the delegated voting power
+    pub receiver: Addr,
+    /// The share of the delegator's voting power that's delegated, in basis points (1/10000)
+    pub bps: u16,
+    /// The period the delegation was made at
+    pub start: u64,
+    /// The period at which the delegation expires and the power reverts to the delegator
+    pub expire_period: u64,
+    /// The voting power carved out of the delegator's point at `start`
+    pub power: Uint128,
+    /// The slope carved out of the delegator's point at `start`
+    pub slope: Decimal,
+}
+
+/// Stores the single active outgoing delegation for each delegator address
+pub const DELEGATIONS: Map<&Addr, Delegation> = Map::new("delegations");
+
+/// A slope adjustment scheduled to take effect on a single address's voting power at a given
+/// period. Unlike [`SLOPE_CHANGES`] (a single contract-wide aggregate), entries here are scoped
+/// to the one address they correct, so they can be replayed on top of that address's own
+/// checkpoints without mixing in unrelated addresses' events.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct SlopeAdjustment {
+    /// Slope restored to the address at this period, e.g. a delegation it made expiring
+    pub slope_increase: Decimal,
+    /// Slope removed from the address at this period, e.g. a delegation it received expiring
+    pub slope_decrease: Decimal,
+    /// Power restored to the address at this period. A delegated slice's power/slope ratio is
+    /// inherited from the delegator's point at grant time, so it only naturally decays to zero at
+    /// the delegator's lock end -- not at `expire_period`, which may be much earlier. This carries
+    /// across whatever power is still outstanding at `expire_period` so it doesn't get stranded.
+    pub power_increase: Uint128,
+    /// Power removed from the address at this period, the counterpart of `power_increase` on the
+    /// side that received the delegation
+    pub power_decrease: Uint128,
+}
+
+/// Stores, per address, the delegation-driven power and slope adjustments scheduled at future
+/// periods. Applied on top of the address's last [`HISTORY`]/[`ANCHOR`] checkpoint by
+/// [`crate::utils::effective_point`] so a delegation reverts automatically at `expire_period` --
+/// both the power still outstanding and the decay rate it carried -- without requiring the
+/// delegator to call [`crate::msg::ExecuteMsg::Undelegate`].
+pub const ADDR_SLOPE_CHANGES: Map<(Addr, U64Key), SlopeAdjustment> = Map::new("addr_slope_changes");
+
+/// GLOBAL_HISTORY mirrors [`HISTORY`] but tracks the aggregate voting power and slope across
+/// every address, so total-power queries don't need to replay every address's checkpoints
+pub const GLOBAL_HISTORY: Map<U64Key, Point> = Map::new("global_history");
+
+/// Governs what happens to a funded period's reward pool if nobody held any voting power then
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ZeroPowerPolicy {
+    /// Roll the period's reward pool forward into the next period
+    RollForward,
+    /// The period's reward pool is forfeited
+    Burn,
+}
+
+/// This structure stores the parameters for the rewards/bribe distribution module
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RewardsConfig {
+    /// The CW20 token rewards are paid out in
+    pub reward_token_addr: Addr,
+    /// What to do with a period's reward pool when nobody had voting power during it
+    pub zero_power_policy: ZeroPowerPolicy,
+    /// The maximum number of periods processed in a single [`crate::msg::ExecuteMsg::ClaimRewards`] call
+    pub max_periods_per_claim: u64,
+}
+
+pub const REWARDS_CONFIG: Item<RewardsConfig> = Item::new("rewards_config");
+
+/// This structure stores the funded reward pool and (once known) the total voting power for a
+/// single weekly period
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PeriodReward {
+    /// The total amount of reward tokens funded for this period
+    pub total_reward: Uint128,
+    /// The total voting power at this period, computed and cached the first time it's needed
+    pub total_power: Option<Uint128>,
+}
+
+/// Stores the funded reward pool per period
+pub const PERIOD_REWARDS: Map<U64Key, PeriodReward> = Map::new("period_rewards");
+
+/// Stores each address's claim cursor: the last period it has already been paid out for
+pub const LAST_CLAIMED_PERIOD: Map<&Addr, u64> = Map::new("last_claimed_period");
+
+/// A single control point of the piecewise-linear lock-time boost curve. `period_fraction` is
+/// the lock interval as a fraction of `MAX_LOCK_TIME` (0 to 1) and `multiplier` is the
+/// corresponding voting power boost at that fraction.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BoostCurvePoint {
+    pub period_fraction: Decimal,
+    pub multiplier: Decimal,
+}
+
+/// Stores the governance-configurable boost curve `calc_coefficient` interpolates between.
+/// Points must be sorted by `period_fraction`, start at `(0, 1)` and have non-decreasing
+/// `multiplier` values.
+pub const BOOST_CURVE: Item<Vec<BoostCurvePoint>> = Item::new("boost_curve");
@@ -8,7 +8,10 @@ use cosmwasm_std::{
 use cw_storage_plus::{Bound, U64Key};
 use std::convert::TryInto;
 
-use crate::state::{Point, BLACKLIST, CONFIG, HISTORY, SLOPE_CHANGES};
+use crate::state::{
+    BoostCurvePoint, Point, SlopeAdjustment, ADDR_SLOPE_CHANGES, ANCHOR, BLACKLIST, CONFIG,
+    GLOBAL_HISTORY, HISTORY, SLOPE_CHANGES,
+};
 
 /// # Description
 /// Checks the time is within limits
@@ -48,7 +51,7 @@ pub(crate) fn blacklist_check(deps: Deps, addr: &Addr) -> Result<(), ContractErr
 
 /// # Description
 /// Trait is intended for Decimal rounding problem elimination
-trait DecimalRoundedCheckedMul {
+pub(crate) trait DecimalRoundedCheckedMul {
     fn checked_mul(self, other: Uint128) -> Result<Uint128, OverflowError>;
 }
 
@@ -95,21 +98,179 @@ pub(crate) fn calc_voting_power(point: &Point, period: u64) -> Uint128 {
 }
 
 /// # Description
-/// Coefficient calculation where 0 [`WEEK`] equals to 1 and [`MAX_LOCK_TIME`] equals to 2.5.
-pub(crate) fn calc_coefficient(interval: u64) -> Decimal {
-    // coefficient = 1 + 1.5 * (end - start) / MAX_LOCK_TIME
-    Decimal::one() + Decimal::from_ratio(15_u64 * interval, get_period(MAX_LOCK_TIME) * 10)
+/// Coefficient calculation: interpolates `curve` at `interval`'s fraction of [`MAX_LOCK_TIME`].
+/// `curve` is assumed to already be a valid boost curve (see [`validate_boost_curve`]).
+pub(crate) fn calc_coefficient(interval: u64, curve: &[BoostCurvePoint]) -> Decimal {
+    let fraction = Decimal::from_ratio(interval, get_period(MAX_LOCK_TIME));
+
+    for window in curve.windows(2) {
+        let (lo, hi) = (&window[0], &window[1]);
+        if fraction >= lo.period_fraction && fraction <= hi.period_fraction {
+            let span = hi.period_fraction - lo.period_fraction;
+            if span.is_zero() {
+                return lo.multiplier;
+            }
+            let progress = (fraction - lo.period_fraction) / span;
+            return lo.multiplier + progress * (hi.multiplier - lo.multiplier);
+        }
+    }
+
+    curve.last().map(|p| p.multiplier).unwrap_or_else(Decimal::one)
 }
 
 /// # Description
-/// Fetches last checkpoint in [`HISTORY`] for given address.
+/// Validates a governance-submitted boost curve: it must start at `(0, 1)` and have
+/// non-decreasing `period_fraction` and `multiplier` values.
+pub(crate) fn validate_boost_curve(points: &[BoostCurvePoint]) -> Result<(), ContractError> {
+    let first = points.first().ok_or(ContractError::InvalidBoostCurve {})?;
+    if first.period_fraction != Decimal::zero() || first.multiplier != Decimal::one() {
+        return Err(ContractError::InvalidBoostCurve {});
+    }
+
+    for window in points.windows(2) {
+        if window[1].period_fraction < window[0].period_fraction
+            || window[1].multiplier < window[0].multiplier
+        {
+            return Err(ContractError::InvalidBoostCurve {});
+        }
+    }
+
+    Ok(())
+}
+
+/// # Description
+/// Fetches last checkpoint in [`HISTORY`] for given address. If every checkpoint at or before
+/// `period_key` has already been evicted, falls back to that address's [`ANCHOR`] point, which
+/// decays like any genuine checkpoint from its own `start` -- callers needing the effective power
+/// at a later period should go through [`calc_voting_power`] or, if delegations may be involved,
+/// [`voting_power_at`]. Returns `None` for a period earlier than the anchor's own `start`: the
+/// checkpoints evicted before it are no longer stored, so power there can't be reconstructed.
+///
+/// [`ANCHOR`] does not need its own slope-change replay: a lock checkpoint's `slope` is chosen
+/// so the point decays to exactly zero by its own `end`, so nothing besides a delegation (handled
+/// separately via [`ADDR_SLOPE_CHANGES`]) can perturb it between eviction and that natural expiry.
 pub(crate) fn fetch_last_checkpoint(
     deps: Deps,
     addr: &Addr,
     period_key: &U64Key,
 ) -> StdResult<Option<Pair<Point>>> {
-    HISTORY
+    let last = HISTORY
         .prefix(addr.clone())
+        .range(
+            deps.storage,
+            None,
+            Some(Bound::Inclusive(period_key.wrapped.clone())),
+            Order::Ascending,
+        )
+        .last()
+        .transpose()?;
+    if last.is_some() {
+        return Ok(last);
+    }
+
+    let anchor = match ANCHOR.may_load(deps.storage, addr)? {
+        Some(anchor) => anchor,
+        None => return Ok(None),
+    };
+
+    let period_bytes: [u8; 8] = period_key
+        .wrapped
+        .clone()
+        .try_into()
+        .map_err(|_| StdError::generic_err("Deserialization error"))?;
+    let period = u64::from_be_bytes(period_bytes);
+    if anchor.start > period {
+        return Ok(None);
+    }
+
+    Ok(Some((U64Key::new(anchor.start).wrapped, anchor)))
+}
+
+/// Computes `addr`'s effective [`Point`] at `period`: its last [`HISTORY`]/[`ANCHOR`] checkpoint
+/// decayed to `period`, with every intervening [`ADDR_SLOPE_CHANGES`] entry applied to both power
+/// and slope along the way so delegations made or received by `addr` revert automatically at
+/// their `expire_period` -- the power still outstanding as well as the decay rate it carried --
+/// whether or not `addr` has written a checkpoint since. Returned `start` is `period` and `end` is
+/// carried over unchanged from the underlying checkpoint, since a delegation's expiry never
+/// changes when the address's own lock ends.
+///
+/// This is the function anything reading an address's "current" power or slope should use in
+/// place of a raw [`fetch_last_checkpoint`] + [`calc_voting_power`] combination, since those don't
+/// account for a delegation that's already auto-reverted without a fresh checkpoint being written.
+pub(crate) fn effective_point(deps: Deps, addr: &Addr, period: u64) -> StdResult<Option<Point>> {
+    let period_key = U64Key::new(period);
+    let (start, end, mut power, mut slope) = match fetch_last_checkpoint(deps, addr, &period_key)?
+    {
+        Some((_, point)) => (point.start, point.end, point.power, point.slope),
+        None => return Ok(None),
+    };
+
+    let mut cursor = start;
+    for (change_period, adjustment) in fetch_addr_slope_changes(deps, addr, start, period)? {
+        let shift = slope
+            .checked_mul(Uint128::from(change_period - cursor))
+            .unwrap_or_else(|_| Uint128::zero());
+        power = power.checked_sub(shift).unwrap_or_else(|_| Uint128::zero());
+        power = (power + adjustment.power_increase).saturating_sub(adjustment.power_decrease);
+        slope = (slope + adjustment.slope_increase).saturating_sub(adjustment.slope_decrease);
+        cursor = change_period;
+    }
+
+    let shift = slope
+        .checked_mul(Uint128::from(period - cursor))
+        .unwrap_or_else(|_| Uint128::zero());
+    power = power.checked_sub(shift).unwrap_or_else(|_| Uint128::zero());
+
+    Ok(Some(Point {
+        power,
+        start: period,
+        end,
+        slope,
+    }))
+}
+
+/// Computes `addr`'s voting power at `period`. Thin wrapper around [`effective_point`] for callers
+/// that only need the scalar power, not the full decayed point.
+pub(crate) fn voting_power_at(deps: Deps, addr: &Addr, period: u64) -> StdResult<Uint128> {
+    Ok(effective_point(deps, addr, period)?
+        .map(|point| point.power)
+        .unwrap_or_default())
+}
+
+/// # Description
+/// Fetches all of `addr`'s [`ADDR_SLOPE_CHANGES`] entries between `last` and `period`.
+pub(crate) fn fetch_addr_slope_changes(
+    deps: Deps,
+    addr: &Addr,
+    last: u64,
+    period: u64,
+) -> StdResult<Vec<(u64, SlopeAdjustment)>> {
+    ADDR_SLOPE_CHANGES
+        .prefix(addr.clone())
+        .range(
+            deps.storage,
+            Some(Bound::Exclusive(U64Key::new(last).wrapped)),
+            Some(Bound::Inclusive(U64Key::new(period).wrapped)),
+            Order::Ascending,
+        )
+        .map(|item| {
+            let (period_bytes, adjustment) = item?;
+            let period_bytes: [u8; 8] = period_bytes
+                .try_into()
+                .map_err(|_| StdError::generic_err("Deserialization error"))?;
+            Ok((u64::from_be_bytes(period_bytes), adjustment))
+        })
+        .collect()
+}
+
+/// # Description
+/// Fetches last checkpoint in [`GLOBAL_HISTORY`], i.e. the aggregate voting power and slope
+/// across every address.
+pub(crate) fn fetch_last_global_checkpoint(
+    deps: Deps,
+    period_key: &U64Key,
+) -> StdResult<Option<Pair<Point>>> {
+    GLOBAL_HISTORY
         .range(
             deps.storage,
             None,
@@ -157,3 +318,188 @@ pub(crate) fn validate_addresses(deps: Deps, addresses: &[String]) -> StdResult<
         .map(|addr| addr_validate_to_lower(deps.api, addr))
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    /// Once an address's only checkpoint has been evicted into `ANCHOR`, a power query at a later
+    /// period must match the plain unbounded decay of that checkpoint alone -- an unrelated
+    /// address's slope-change event must not leak into the reconstruction, i.e. the anchor is
+    /// never replayed against the contract-wide `SLOPE_CHANGES` map.
+    #[test]
+    fn anchor_fallback_matches_unbounded_decay_and_ignores_unrelated_slope_changes() {
+        let mut deps = mock_dependencies(&[]);
+        let addr = Addr::unchecked("delegator");
+
+        let anchor_point = Point {
+            power: Uint128::new(1000),
+            start: 10,
+            end: 60,
+            slope: Decimal::from_ratio(1000_u128, 50_u128),
+        };
+        ANCHOR
+            .save(deps.as_mut().storage, &addr, &anchor_point)
+            .unwrap();
+
+        // Noise from an unrelated address's own lock expiring at period 20, in between
+        // `anchor.start` and the period queried below.
+        SLOPE_CHANGES
+            .save(
+                deps.as_mut().storage,
+                U64Key::new(20),
+                &Decimal::from_ratio(1000_u128, 50_u128),
+            )
+            .unwrap();
+
+        let queried_period = 30;
+        let (_, point) = fetch_last_checkpoint(deps.as_ref(), &addr, &U64Key::new(queried_period))
+            .unwrap()
+            .unwrap();
+        let power = calc_voting_power(&point, queried_period);
+
+        let expected = anchor_point
+            .power
+            .checked_sub(
+                anchor_point.slope * Uint128::from(queried_period - anchor_point.start),
+            )
+            .unwrap();
+        assert_eq!(power, expected);
+    }
+
+    /// `voting_power_at` must apply a delegation's `ADDR_SLOPE_CHANGES` entries even when the
+    /// address hasn't written a checkpoint since the delegation was made, so the delegated slice
+    /// reverts automatically at `expire_period` on the normal query path.
+    #[test]
+    fn voting_power_at_reverts_delegation_slope_at_expiry() {
+        let mut deps = mock_dependencies(&[]);
+        let addr = Addr::unchecked("delegator");
+
+        let point = Point {
+            power: Uint128::new(500),
+            start: 0,
+            end: 100,
+            slope: Decimal::from_ratio(5_u128, 1_u128),
+        };
+        HISTORY
+            .save(deps.as_mut().storage, (addr.clone(), U64Key::new(0)), &point)
+            .unwrap();
+
+        // Half the slope was carved out by a delegation expiring at period 40; it's scheduled to
+        // be restored to the delegator at that period.
+        ADDR_SLOPE_CHANGES
+            .save(
+                deps.as_mut().storage,
+                (addr.clone(), U64Key::new(40)),
+                &SlopeAdjustment {
+                    slope_increase: Decimal::from_ratio(5_u128, 1_u128),
+                    slope_decrease: Decimal::zero(),
+                    power_increase: Uint128::zero(),
+                    power_decrease: Uint128::zero(),
+                },
+            )
+            .unwrap();
+
+        // Before expiry: decays at the reduced slope alone.
+        let before = voting_power_at(deps.as_ref(), &addr, 20).unwrap();
+        assert_eq!(before, Uint128::new(500) - Uint128::new(5) * Uint128::new(20));
+
+        // After expiry: the slope steepens back to 10/period at period 40, so the remaining decay
+        // from 40 to 50 uses the full slope, not the carved-out one.
+        let power_at_40 = Uint128::new(500) - Uint128::new(5) * Uint128::new(40);
+        let after = voting_power_at(deps.as_ref(), &addr, 50).unwrap();
+        assert_eq!(after, power_at_40 - Uint128::new(10) * Uint128::new(10));
+    }
+
+    /// `calc_coefficient` must interpolate linearly between the surrounding curve points, land
+    /// exactly on a control point's multiplier when `interval` matches its `period_fraction`, and
+    /// clamp to the last point's multiplier once `interval` exceeds the curve's range.
+    #[test]
+    fn calc_coefficient_interpolates_piecewise_and_clamps_past_the_end() {
+        let max_lock_periods = get_period(MAX_LOCK_TIME);
+        let curve = vec![
+            BoostCurvePoint {
+                period_fraction: Decimal::zero(),
+                multiplier: Decimal::one(),
+            },
+            BoostCurvePoint {
+                period_fraction: Decimal::from_ratio(1_u128, 2_u128),
+                multiplier: Decimal::from_ratio(2_u128, 1_u128),
+            },
+            BoostCurvePoint {
+                period_fraction: Decimal::one(),
+                multiplier: Decimal::from_ratio(4_u128, 1_u128),
+            },
+        ];
+
+        // Halfway to the midpoint control point: interpolates within the first leg.
+        let quarter = max_lock_periods / 4;
+        assert_eq!(
+            calc_coefficient(quarter, &curve),
+            Decimal::from_ratio(3_u128, 2_u128),
+        );
+
+        // Exactly on the midpoint control point.
+        let half = max_lock_periods / 2;
+        assert_eq!(calc_coefficient(half, &curve), Decimal::from_ratio(2_u128, 1_u128));
+
+        // Three quarters of the way: interpolates within the second leg.
+        let three_quarters = max_lock_periods * 3 / 4;
+        assert_eq!(
+            calc_coefficient(three_quarters, &curve),
+            Decimal::from_ratio(3_u128, 1_u128),
+        );
+
+        // Past the curve's range entirely: clamps to the last point's multiplier.
+        assert_eq!(
+            calc_coefficient(max_lock_periods * 2, &curve),
+            Decimal::from_ratio(4_u128, 1_u128),
+        );
+    }
+
+    /// A boost curve must be rejected unless it starts at `(0, 1)` and has non-decreasing
+    /// `period_fraction` and `multiplier` values throughout.
+    #[test]
+    fn validate_boost_curve_rejects_bad_starting_point_and_non_monotonic_curves() {
+        let valid = vec![
+            BoostCurvePoint {
+                period_fraction: Decimal::zero(),
+                multiplier: Decimal::one(),
+            },
+            BoostCurvePoint {
+                period_fraction: Decimal::one(),
+                multiplier: Decimal::from_ratio(4_u128, 1_u128),
+            },
+        ];
+        assert!(validate_boost_curve(&valid).is_ok());
+
+        let wrong_start = vec![
+            BoostCurvePoint {
+                period_fraction: Decimal::zero(),
+                multiplier: Decimal::from_ratio(2_u128, 1_u128),
+            },
+            BoostCurvePoint {
+                period_fraction: Decimal::one(),
+                multiplier: Decimal::from_ratio(4_u128, 1_u128),
+            },
+        ];
+        assert!(validate_boost_curve(&wrong_start).is_err());
+
+        let decreasing_multiplier = vec![
+            BoostCurvePoint {
+                period_fraction: Decimal::zero(),
+                multiplier: Decimal::one(),
+            },
+            BoostCurvePoint {
+                period_fraction: Decimal::from_ratio(1_u128, 2_u128),
+                multiplier: Decimal::from_ratio(3_u128, 1_u128),
+            },
+            BoostCurvePoint {
+                period_fraction: Decimal::one(),
+                multiplier: Decimal::from_ratio(2_u128, 1_u128),
+            },
+        ];
+        assert!(validate_boost_curve(&decreasing_multiplier).is_err());
+    }
+}